@@ -0,0 +1,296 @@
+//! Batch ingestion of an entire mail store (a Maildir tree or an mbox file)
+//!
+//! Messages are parsed in parallel with rayon since each file is
+//! independent, then converted to Markdown and optionally indexed in a
+//! SQLite database for fast querying of the resulting corpus.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use rusqlite::Connection;
+
+use crate::eml::parse_eml_bytes;
+use crate::formatter::format_markdown;
+
+/// Where the messages for a batch run come from
+pub enum MailSource {
+    /// A Maildir directory tree (`cur/` and `new/` subdirectories)
+    Maildir(PathBuf),
+    /// A single mbox file containing one or more `From `-delimited messages
+    Mbox(PathBuf),
+}
+
+/// Per-message metadata recorded in the optional SQLite index
+#[derive(Debug, Clone)]
+pub struct MessageIndexEntry {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub date: Option<String>,
+    pub message_id: String,
+    pub output_path: PathBuf,
+}
+
+/// Summary of a completed batch run
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub total: usize,
+    pub succeeded: usize,
+    /// `(message label, error message)` for messages that failed to convert
+    pub failed: Vec<(String, String)>,
+}
+
+/// Convert every message in `source` to Markdown under `output_dir`,
+/// optionally recording per-message metadata in a SQLite index at
+/// `index_db`. A message that fails to parse or whose Markdown file cannot
+/// be written is reported in the returned `BatchReport` rather than
+/// aborting the whole run. An error while reading the source itself (a
+/// Maildir directory or one of its message files, or the mbox file) is not
+/// collected per message: it aborts the run and is returned as the error.
+pub fn run_batch(
+    source: MailSource,
+    output_dir: &Path,
+    formatter_name: &str,
+    index_db: Option<&Path>,
+) -> Result<BatchReport> {
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create output directory: {}", output_dir.display()))?;
+
+    let messages = match &source {
+        MailSource::Maildir(dir) => collect_maildir_messages(dir)?,
+        MailSource::Mbox(path) => split_mbox(path)?,
+    };
+
+    let mut report = BatchReport {
+        total: messages.len(),
+        ..Default::default()
+    };
+
+    let converted: Vec<(String, Result<(String, MessageIndexEntry)>)> = messages
+        .par_iter()
+        .map(|(label, content)| {
+            let result = convert_message(label, content, output_dir, formatter_name);
+            (label.clone(), result)
+        })
+        .collect();
+
+    let mut index_entries = Vec::new();
+
+    for (label, result) in converted {
+        match result {
+            Ok((markdown, entry)) => {
+                match fs::write(&entry.output_path, &markdown).with_context(|| {
+                    format!("Failed to write {}", entry.output_path.display())
+                }) {
+                    Ok(()) => {
+                        report.succeeded += 1;
+                        index_entries.push(entry);
+                    }
+                    Err(err) => report.failed.push((label, err.to_string())),
+                }
+            }
+            Err(err) => report.failed.push((label, err.to_string())),
+        }
+    }
+
+    if let Some(db_path) = index_db {
+        write_index(db_path, &index_entries)?;
+    }
+
+    Ok(report)
+}
+
+/// Parse and format a single message, computing its output path and index entry
+fn convert_message(
+    label: &str,
+    content: &[u8],
+    output_dir: &Path,
+    formatter_name: &str,
+) -> Result<(String, MessageIndexEntry)> {
+    let email = parse_eml_bytes(content).with_context(|| format!("Failed to parse {}", label))?;
+    let markdown = format_markdown(&email, formatter_name);
+
+    let output_path = output_dir.join(format!("{}.md", sanitize_filename(label)));
+
+    let entry = MessageIndexEntry {
+        from: email.header.from.email.clone(),
+        to: email
+            .header
+            .to
+            .iter()
+            .map(|user| user.email.clone())
+            .collect::<Vec<_>>()
+            .join(", "),
+        subject: email.header.subject.clone(),
+        date: email.header.date.map(|date| date.to_rfc3339()),
+        message_id: email.header.message_id.clone().unwrap_or_default(),
+        output_path,
+    };
+
+    Ok((markdown, entry))
+}
+
+/// Replace characters that are awkward in file names with `_`
+fn sanitize_filename(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+/// Walk a Maildir tree's `cur/` and `new/` subdirectories (messages in
+/// `tmp/` are still being delivered and are skipped), returning each
+/// message's file name and raw content
+fn collect_maildir_messages(dir: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut messages = Vec::new();
+
+    for subdir in ["cur", "new"] {
+        let path = dir.join(subdir);
+        if !path.is_dir() {
+            continue;
+        }
+
+        for entry in fs::read_dir(&path)
+            .with_context(|| format!("Failed to read directory: {}", path.display()))?
+        {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            let label = entry.file_name().to_string_lossy().into_owned();
+            let content = fs::read(entry.path())
+                .with_context(|| format!("Failed to read file: {}", entry.path().display()))?;
+            messages.push((label, content));
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Split an mbox file into its individual messages on `From ` envelope
+/// lines, labeling each by its position in the file
+fn split_mbox(path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let content =
+        fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+    Ok(split_mbox_content(&content))
+}
+
+/// Split raw mbox bytes into individual messages on `From ` envelope lines
+fn split_mbox_content(content: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut messages = Vec::new();
+    let mut current_start: Option<usize> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive(|&b| b == b'\n') {
+        if is_mbox_from_line(line) {
+            if let Some(start) = current_start {
+                push_mbox_message(&mut messages, &content[start..offset]);
+            }
+            current_start = Some(offset);
+        } else if current_start.is_none() {
+            current_start = Some(offset);
+        }
+        offset += line.len();
+    }
+
+    if let Some(start) = current_start {
+        push_mbox_message(&mut messages, &content[start..]);
+    }
+
+    messages
+}
+
+fn is_mbox_from_line(line: &[u8]) -> bool {
+    line.starts_with(b"From ")
+}
+
+fn push_mbox_message(messages: &mut Vec<(String, Vec<u8>)>, raw: &[u8]) {
+    let trimmed = strip_mbox_from_line(raw);
+    if trimmed.iter().all(|b| b.is_ascii_whitespace()) {
+        return;
+    }
+    let label = format!("message-{}", messages.len() + 1);
+    messages.push((label, trimmed.to_vec()));
+}
+
+fn strip_mbox_from_line(raw: &[u8]) -> &[u8] {
+    if is_mbox_from_line(raw) {
+        match raw.iter().position(|&b| b == b'\n') {
+            Some(idx) => &raw[idx + 1..],
+            None => &[],
+        }
+    } else {
+        raw
+    }
+}
+
+/// Create (if needed) the `messages` table and insert every entry in a
+/// single transaction
+fn write_index(db_path: &Path, entries: &[MessageIndexEntry]) -> Result<()> {
+    let mut conn = Connection::open(db_path)
+        .with_context(|| format!("Failed to open index database: {}", db_path.display()))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS messages (
+            id INTEGER PRIMARY KEY,
+            from_addr TEXT NOT NULL,
+            to_addr TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            date TEXT,
+            message_id TEXT NOT NULL,
+            output_path TEXT NOT NULL
+        )",
+        (),
+    )?;
+
+    let tx = conn.transaction()?;
+    for entry in entries {
+        tx.execute(
+            "INSERT INTO messages (from_addr, to_addr, subject, date, message_id, output_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            (
+                &entry.from,
+                &entry.to,
+                &entry.subject,
+                &entry.date,
+                &entry.message_id,
+                &entry.output_path.to_string_lossy(),
+            ),
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("1700000000.M123P456.host,S=1234"), "1700000000.M123P456.host_S_1234");
+    }
+
+    #[test]
+    fn test_split_mbox_single_message() {
+        let mbox = b"From sender@example.com Mon Jan 1 00:00:00 2024\r\nSubject: Hi\r\n\r\nBody\r\n";
+        let messages = split_mbox_content(mbox);
+        assert_eq!(messages.len(), 1);
+        assert!(String::from_utf8_lossy(&messages[0].1).contains("Subject: Hi"));
+    }
+
+    #[test]
+    fn test_split_mbox_multiple_messages() {
+        let mbox = b"From a@example.com Mon Jan 1 00:00:00 2024\r\nSubject: One\r\n\r\nBody one\r\n\
+                     From b@example.com Mon Jan 1 00:00:01 2024\r\nSubject: Two\r\n\r\nBody two\r\n";
+        let messages = split_mbox_content(mbox);
+        assert_eq!(messages.len(), 2);
+        assert!(String::from_utf8_lossy(&messages[0].1).contains("Subject: One"));
+        assert!(String::from_utf8_lossy(&messages[1].1).contains("Subject: Two"));
+    }
+}
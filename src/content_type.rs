@@ -1,4 +1,4 @@
-//! Content-Type header parsing utilities
+//! Content-Type and Content-Disposition header parsing utilities
 
 use std::collections::HashMap;
 
@@ -26,7 +26,6 @@ impl ContentType {
     /// assert_eq!(ct.parameters.get("name"), Some(&"test.png".to_string()));
     /// ```
     pub fn parse(content_type: &str) -> Self {
-        let mut parameters = HashMap::new();
         let tokens: Vec<&str> = content_type.split(';').map(|s| s.trim()).collect();
 
         let (main_type, sub_type) = if let Some(type_part) = tokens.first() {
@@ -40,12 +39,7 @@ impl ContentType {
             (String::new(), String::new())
         };
 
-        for token in tokens.iter().skip(1) {
-            if let Some((key, val)) = token.split_once('=') {
-                let val = val.trim_matches('"').to_string();
-                parameters.insert(key.trim().to_string(), val);
-            }
-        }
+        let parameters = parse_parameters(tokens.get(1..).unwrap_or_default());
 
         ContentType {
             main_type,
@@ -60,6 +54,200 @@ impl ContentType {
     }
 }
 
+/// Parsed Content-Disposition header
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentDisposition {
+    /// Disposition type (e.g., "inline", "attachment")
+    pub disposition_type: String,
+    /// Parameters (e.g., filename="image.png")
+    pub parameters: HashMap<String, String>,
+}
+
+impl ContentDisposition {
+    /// Parse a Content-Disposition header string
+    ///
+    /// # Example
+    /// ```
+    /// use eml2md::content_type::ContentDisposition;
+    ///
+    /// let cd = ContentDisposition::parse("attachment; filename=\"test.png\"");
+    /// assert_eq!(cd.disposition_type, "attachment");
+    /// assert_eq!(cd.filename(), Some(&"test.png".to_string()));
+    /// ```
+    pub fn parse(content_disposition: &str) -> Self {
+        let tokens: Vec<&str> = content_disposition.split(';').map(|s| s.trim()).collect();
+
+        let disposition_type = tokens.first().map(|s| s.to_string()).unwrap_or_default();
+        let parameters = parse_parameters(tokens.get(1..).unwrap_or_default());
+
+        ContentDisposition {
+            disposition_type,
+            parameters,
+        }
+    }
+
+    /// Whether this is an `inline` disposition
+    pub fn is_inline(&self) -> bool {
+        self.disposition_type.eq_ignore_ascii_case("inline")
+    }
+
+    /// Whether this is an `attachment` disposition
+    pub fn is_attachment(&self) -> bool {
+        self.disposition_type.eq_ignore_ascii_case("attachment")
+    }
+
+    /// Convenience accessor for the `filename` parameter
+    pub fn filename(&self) -> Option<&String> {
+        self.parameters.get("filename")
+    }
+}
+
+/// Parse a `key=value` parameter list, handling RFC 2231 extended parameters
+/// (`name*=charset'language'percent-encoded-text`) and continuations
+/// (`name*0`, `name*1`, ... and `name*0*`, `name*1*`, ...).
+fn parse_parameters(tokens: &[&str]) -> HashMap<String, String> {
+    let mut raw: Vec<(String, String)> = Vec::new();
+    for token in tokens {
+        if let Some((key, val)) = token.split_once('=') {
+            raw.push((key.trim().to_string(), val.trim().to_string()));
+        }
+    }
+
+    let mut parameters = HashMap::new();
+    let mut continuations: HashMap<String, Vec<(u32, bool, String)>> = HashMap::new();
+
+    for (key, val) in &raw {
+        if let Some(base) = key.strip_suffix('*') {
+            if let Some((base_name, idx)) = split_continuation_index(base) {
+                continuations
+                    .entry(base_name)
+                    .or_default()
+                    .push((idx, true, val.clone()));
+            } else {
+                parameters.insert(base.to_string(), decode_extended_value(val));
+            }
+        } else if let Some((base_name, idx)) = split_continuation_index(key) {
+            continuations
+                .entry(base_name)
+                .or_default()
+                .push((idx, false, val.clone()));
+        } else {
+            parameters.insert(key.clone(), unquote(val));
+        }
+    }
+
+    for (name, mut segments) in continuations {
+        segments.sort_by_key(|(idx, _, _)| *idx);
+
+        let mut charset: Option<String> = None;
+        let mut bytes = Vec::new();
+
+        for (idx, extended, value) in &segments {
+            if *extended {
+                let text = if *idx == 0 {
+                    let (cs, text) = split_charset_language(value);
+                    charset = cs;
+                    text
+                } else {
+                    value.clone()
+                };
+                bytes.extend(percent_decode(&text));
+            } else {
+                bytes.extend(unquote(value).into_bytes());
+            }
+        }
+
+        let decoded = decode_with_charset(&bytes, charset.as_deref().unwrap_or("utf-8"));
+        parameters.insert(name, decoded);
+    }
+
+    parameters
+}
+
+/// Split a continuation key like `name*0` into (`name`, `0`). Returns `None`
+/// for plain keys with no numeric `*N` suffix.
+fn split_continuation_index(key: &str) -> Option<(String, u32)> {
+    let (base, idx) = key.rsplit_once('*')?;
+    if base.is_empty() || idx.is_empty() || !idx.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    idx.parse::<u32>().ok().map(|n| (base.to_string(), n))
+}
+
+/// Decode a single RFC 2231 extended parameter value
+/// (`charset'language'percent-encoded-text`).
+fn decode_extended_value(value: &str) -> String {
+    let (charset, text) = split_charset_language(value);
+    let bytes = percent_decode(&text);
+    decode_with_charset(&bytes, charset.as_deref().unwrap_or("utf-8"))
+}
+
+/// Split `charset'language'text` into (`charset`, `text`). Falls back to
+/// treating the whole value as text when it isn't in that form.
+fn split_charset_language(value: &str) -> (Option<String>, String) {
+    let first = match value.find('\'') {
+        Some(i) => i,
+        None => return (None, value.to_string()),
+    };
+    let second = match value[first + 1..].find('\'') {
+        Some(j) => first + 1 + j,
+        None => return (None, value.to_string()),
+    };
+
+    let charset = &value[..first];
+    let text = &value[second + 1..];
+    let charset = if charset.is_empty() {
+        None
+    } else {
+        Some(charset.to_string())
+    };
+    (charset, text.to_string())
+}
+
+/// Percent-decode a string into raw bytes, leaving malformed `%` sequences
+/// untouched.
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex_digit(bytes[i + 1]), hex_digit(bytes[i + 2])) {
+                out.push(hi * 16 + lo);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+fn hex_digit(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decode bytes using the declared charset, falling back to lossy UTF-8 for
+/// charsets we don't special-case.
+fn decode_with_charset(bytes: &[u8], charset: &str) -> String {
+    match charset.to_ascii_lowercase().as_str() {
+        "iso-8859-1" | "latin1" | "us-ascii" | "ascii" => {
+            bytes.iter().map(|&b| b as char).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches('"').to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +268,46 @@ mod tests {
         assert_eq!(ct.parameters.get("name"), Some(&"test.png".to_string()));
         assert_eq!(ct.parameters.get("charset"), Some(&"utf-8".to_string()));
     }
+
+    #[test]
+    fn test_parse_rfc2231_extended_value() {
+        let ct = ContentType::parse("application/pdf; name*=utf-8''%e6%97%a5%e6%9c%ac.pdf");
+        assert_eq!(ct.parameters.get("name"), Some(&"日本.pdf".to_string()));
+    }
+
+    #[test]
+    fn test_parse_rfc2231_plain_continuations() {
+        let ct = ContentType::parse(
+            "application/pdf; name*0=\"long file\"; name*1=\"name.pdf\"",
+        );
+        assert_eq!(
+            ct.parameters.get("name"),
+            Some(&"long filename.pdf".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc2231_encoded_continuations() {
+        let ct = ContentType::parse(
+            "application/pdf; name*0*=utf-8''%e6%97%a5%e6%9c%ac; name*1*=%e8%aa%9e.pdf",
+        );
+        assert_eq!(ct.parameters.get("name"), Some(&"日本語.pdf".to_string()));
+    }
+
+    #[test]
+    fn test_content_disposition_inline() {
+        let cd = ContentDisposition::parse("inline; filename=\"cid_image.png\"");
+        assert!(cd.is_inline());
+        assert!(!cd.is_attachment());
+        assert_eq!(cd.filename(), Some(&"cid_image.png".to_string()));
+    }
+
+    #[test]
+    fn test_content_disposition_attachment_rfc2231() {
+        let cd = ContentDisposition::parse(
+            "attachment; filename*=utf-8''%e6%97%a5%e6%9c%ac.pdf",
+        );
+        assert!(cd.is_attachment());
+        assert_eq!(cd.filename(), Some(&"日本.pdf".to_string()));
+    }
 }
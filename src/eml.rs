@@ -1,12 +1,13 @@
 //! EML file parsing module
 
+use std::collections::HashMap;
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, FixedOffset};
-use mail_parser::{MessageParser, MimeHeaders};
+use mail_parser::{Message, MessageParser, MimeHeaders, PartType};
 
-use crate::content_type::ContentType;
+use crate::content_type::{ContentDisposition, ContentType};
 
 /// Represents an email address with name and address
 #[derive(Debug, Clone, PartialEq)]
@@ -32,6 +33,30 @@ pub struct Header {
     pub cc: Vec<User>,
     pub subject: String,
     pub date: Option<DateTime<FixedOffset>>,
+    pub message_id: Option<String>,
+}
+
+/// Multipart container semantics, as distinguished by a mail library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultipartKind {
+    /// `multipart/mixed`: parts are independent content, concatenated
+    Mixed,
+    /// `multipart/alternative`: parts are different renditions of the same content
+    Alternative,
+    /// `multipart/related`: parts reference each other (e.g. an HTML body and its inline images)
+    Related,
+}
+
+impl MultipartKind {
+    /// Map a multipart subtype (e.g. "alternative") to its semantics
+    fn from_subtype(subtype: &str) -> Option<Self> {
+        match subtype.to_ascii_lowercase().as_str() {
+            "mixed" => Some(Self::Mixed),
+            "alternative" => Some(Self::Alternative),
+            "related" => Some(Self::Related),
+            _ => None,
+        }
+    }
 }
 
 /// Email body content
@@ -39,6 +64,10 @@ pub struct Header {
 pub struct Body {
     pub content: String,
     pub content_type: String,
+    /// Identifies the `multipart/alternative` group this body belongs to, if
+    /// any. Bodies sharing the same group are different renditions of the
+    /// same content, and a `Formatter` should emit only one of them.
+    pub alternative_group: Option<usize>,
 }
 
 /// Email attachment
@@ -47,6 +76,24 @@ pub struct Attachment {
     pub raw: Vec<u8>,
     pub content_type: Option<ContentType>,
     pub content_id: Option<String>,
+    pub content_disposition: Option<ContentDisposition>,
+}
+
+impl Attachment {
+    /// The attachment's file name, preferring the `Content-Disposition`
+    /// `filename` parameter over the `Content-Type` `name` parameter, per
+    /// RFC 2183.
+    pub fn filename(&self) -> Option<&str> {
+        self.content_disposition
+            .as_ref()
+            .and_then(ContentDisposition::filename)
+            .or_else(|| {
+                self.content_type
+                    .as_ref()
+                    .and_then(|ct| ct.parameters.get("name"))
+            })
+            .map(String::as_str)
+    }
 }
 
 /// Parsed email structure
@@ -124,31 +171,45 @@ pub fn parse_eml_bytes(content: &[u8]) -> Result<Email> {
             .map(|utc| utc.with_timezone(&FixedOffset::east_opt(0).unwrap()))
     });
 
+    // Parse Message-ID
+    let message_id = message.message_id().map(|id| id.to_string());
+
     let header = Header {
         from,
         to,
         cc,
         subject,
         date,
+        message_id,
     };
 
     // Parse body parts
     let mut body = Vec::new();
     let mut attachments = Vec::new();
 
+    // Walk the actual MIME part tree so that a `multipart/alternative`
+    // nested anywhere (e.g. under a `multipart/mixed` root alongside an
+    // attachment, the most common real-world shape) is still recognized,
+    // rather than only checking the outermost Content-Type.
+    let groups = alternative_groups(&message);
+
     // Get text body
     if let Some(text) = message.body_text(0) {
+        let alternative_group = message.text_body.first().and_then(|id| groups.get(id)).copied();
         body.push(Body {
             content: text.to_string(),
             content_type: "text/plain".to_string(),
+            alternative_group,
         });
     }
 
     // Get HTML body
     if let Some(html) = message.body_html(0) {
+        let alternative_group = message.html_body.first().and_then(|id| groups.get(id)).copied();
         body.push(Body {
             content: html.to_string(),
             content_type: "text/html".to_string(),
+            alternative_group,
         });
     }
 
@@ -157,14 +218,33 @@ pub fn parse_eml_bytes(content: &[u8]) -> Result<Email> {
         let content_type = attachment
             .content_type()
             .map(|ct: &mail_parser::ContentType| {
-                let type_str = format!("{}/{}", ct.ctype(), ct.subtype().unwrap_or_default());
-                ContentType::parse(&type_str)
+                let mut header = format!("{}/{}", ct.ctype(), ct.subtype().unwrap_or_default());
+                for (name, value) in ct.attributes().unwrap_or_default() {
+                    header.push_str(&format!("; {}=\"{}\"", name, value.replace('"', "\\\"")));
+                }
+                ContentType::parse(&header)
             });
 
+        let content_id = attachment
+            .content_id()
+            .map(|cid| cid.trim_start_matches('<').trim_end_matches('>').to_string());
+
+        let content_disposition =
+            attachment
+                .content_disposition()
+                .map(|cd: &mail_parser::ContentType| {
+                    let mut header = cd.ctype().to_string();
+                    if let Some(filename) = cd.attribute("filename") {
+                        header.push_str(&format!("; filename=\"{}\"", filename.replace('"', "\\\"")));
+                    }
+                    ContentDisposition::parse(&header)
+                });
+
         attachments.push(Attachment {
             raw: attachment.contents().to_vec(),
             content_type,
-            content_id: None,
+            content_id,
+            content_disposition,
         });
     }
 
@@ -175,6 +255,54 @@ pub fn parse_eml_bytes(content: &[u8]) -> Result<Email> {
     })
 }
 
+/// Walk the MIME part tree, mapping each `text/plain`/`text/html` leaf part
+/// index to the part index of its nearest `multipart/alternative` ancestor,
+/// if it has one. Parts under a `multipart/mixed` or `multipart/related`
+/// ancestor simply inherit whatever alternative group (if any) encloses
+/// that ancestor, since those containers don't introduce alternative
+/// renditions of their own.
+fn alternative_groups(message: &Message<'_>) -> HashMap<usize, usize> {
+    let mut groups = HashMap::new();
+    walk_parts(message, 0, None, &mut groups);
+    groups
+}
+
+fn walk_parts(
+    message: &Message<'_>,
+    part_id: usize,
+    enclosing_alternative: Option<usize>,
+    groups: &mut HashMap<usize, usize>,
+) {
+    let Some(part) = message.parts.get(part_id) else {
+        return;
+    };
+
+    let children = match &part.body {
+        PartType::Multipart(children) => children,
+        PartType::Text(_) | PartType::Html(_) => {
+            if let Some(group) = enclosing_alternative {
+                groups.insert(part_id, group);
+            }
+            return;
+        }
+        _ => return,
+    };
+
+    let kind = part
+        .content_type()
+        .and_then(|ct| ct.subtype())
+        .and_then(MultipartKind::from_subtype);
+
+    let group_for_children = match kind {
+        Some(MultipartKind::Alternative) => Some(part_id),
+        Some(MultipartKind::Mixed) | Some(MultipartKind::Related) | None => enclosing_alternative,
+    };
+
+    for &child_id in children {
+        walk_parts(message, child_id, group_for_children, groups);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,4 +328,108 @@ mod tests {
             "Email should contain text/plain body"
         );
     }
+
+    #[test]
+    fn test_parse_content_id() {
+        let eml = b"From: sender@example.com\r\n\
+                    To: recipient@example.com\r\n\
+                    Subject: Inline image\r\n\
+                    Content-Type: multipart/related; boundary=\"b\"\r\n\r\n\
+                    --b\r\n\
+                    Content-Type: text/html\r\n\r\n\
+                    <img src=\"cid:logo@example.com\">\r\n\
+                    --b\r\n\
+                    Content-Type: image/png\r\n\
+                    Content-ID: <logo@example.com>\r\n\
+                    Content-Transfer-Encoding: base64\r\n\r\n\
+                    aGVsbG8=\r\n\
+                    --b--\r\n";
+
+        let email = parse_eml_bytes(eml).unwrap();
+        assert_eq!(
+            email.attachments[0].content_id,
+            Some("logo@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_attachment_filename_from_content_type_name_param() {
+        // No Content-Disposition header at all: the Content-Type `name`
+        // parameter is the only source of a filename.
+        let eml = b"From: sender@example.com\r\n\
+                    To: recipient@example.com\r\n\
+                    Subject: Inline image\r\n\
+                    Content-Type: multipart/related; boundary=\"b\"\r\n\r\n\
+                    --b\r\n\
+                    Content-Type: text/html\r\n\r\n\
+                    <p>Body</p>\r\n\
+                    --b\r\n\
+                    Content-Type: image/png; name=\"photo.png\"\r\n\
+                    Content-Transfer-Encoding: base64\r\n\r\n\
+                    aGVsbG8=\r\n\
+                    --b--\r\n";
+
+        let email = parse_eml_bytes(eml).unwrap();
+        assert_eq!(
+            email.attachments[0].filename(),
+            Some("photo.png")
+        );
+    }
+
+    #[test]
+    fn test_alternative_bodies_share_a_group() {
+        let eml = b"From: sender@example.com\r\n\
+                    To: recipient@example.com\r\n\
+                    Subject: Alternative\r\n\
+                    Content-Type: multipart/alternative; boundary=\"b\"\r\n\r\n\
+                    --b\r\n\
+                    Content-Type: text/plain\r\n\r\n\
+                    Plain\r\n\
+                    --b\r\n\
+                    Content-Type: text/html\r\n\r\n\
+                    <p>HTML</p>\r\n\
+                    --b--\r\n";
+
+        let email = parse_eml_bytes(eml).unwrap();
+        assert_eq!(email.body.len(), 2);
+        assert!(email.body[0].alternative_group.is_some());
+        assert_eq!(
+            email.body[0].alternative_group,
+            email.body[1].alternative_group
+        );
+    }
+
+    #[test]
+    fn test_alternative_bodies_grouped_under_mixed_with_attachment() {
+        // The overwhelmingly common real-world shape: multipart/mixed
+        // (because of the attachment) wrapping a multipart/alternative
+        // text/html pair.
+        let eml = b"From: sender@example.com\r\n\
+                    To: recipient@example.com\r\n\
+                    Subject: Alternative with attachment\r\n\
+                    Content-Type: multipart/mixed; boundary=\"m\"\r\n\r\n\
+                    --m\r\n\
+                    Content-Type: multipart/alternative; boundary=\"a\"\r\n\r\n\
+                    --a\r\n\
+                    Content-Type: text/plain\r\n\r\n\
+                    Plain\r\n\
+                    --a\r\n\
+                    Content-Type: text/html\r\n\r\n\
+                    <p>HTML</p>\r\n\
+                    --a--\r\n\
+                    --m\r\n\
+                    Content-Type: application/pdf; name=\"report.pdf\"\r\n\
+                    Content-Transfer-Encoding: base64\r\n\r\n\
+                    aGVsbG8=\r\n\
+                    --m--\r\n";
+
+        let email = parse_eml_bytes(eml).unwrap();
+        assert_eq!(email.body.len(), 2);
+        assert!(email.body[0].alternative_group.is_some());
+        assert_eq!(
+            email.body[0].alternative_group,
+            email.body[1].alternative_group
+        );
+        assert_eq!(email.attachments.len(), 1);
+    }
 }
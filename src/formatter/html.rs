@@ -0,0 +1,669 @@
+//! A small HTML-to-Markdown renderer for `text/html` bodies
+//!
+//! This is a purpose-built subset parser, not a general HTML engine: it
+//! tokenizes tags/attributes/text, builds a lightweight tree (auto-closing
+//! unclosed tags as it goes), then walks that tree mapping the handful of
+//! elements mail bodies actually use to their Markdown equivalents.
+
+use std::collections::HashMap;
+
+use crate::eml::Email;
+
+/// Render an HTML email body as Markdown
+pub(crate) fn html_to_markdown(html: &str, email: &Email) -> String {
+    let tokens = tokenize(html);
+    let tree = build_tree(tokens);
+    let mut list_stack = Vec::new();
+    let rendered = render_nodes(&tree, email, &mut list_stack);
+    collapse_blank_lines(rendered.trim())
+}
+
+enum Token {
+    Start {
+        name: String,
+        attrs: HashMap<String, String>,
+        self_closing: bool,
+    },
+    End {
+        name: String,
+    },
+    Text(String),
+}
+
+enum Node {
+    Element {
+        tag: String,
+        attrs: HashMap<String, String>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+struct ListCtx {
+    ordered: bool,
+    index: u32,
+}
+
+const VOID_ELEMENTS: &[&str] = &[
+    "br", "img", "hr", "meta", "link", "input", "area", "base", "col", "embed", "source", "track",
+    "wbr",
+];
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let chars: Vec<char> = html.chars().collect();
+    let mut tokens = Vec::new();
+    let mut text_buf = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] != '<' {
+            text_buf.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        if !text_buf.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text_buf)));
+        }
+
+        // Comments: <!-- ... -->
+        if matches_at(&chars, i, "<!--") {
+            match find_sequence(&chars, i + 4, "-->") {
+                Some(end) => {
+                    i = end + 3;
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        // Other declarations/doctype: <! ... >
+        if i + 1 < chars.len() && chars[i + 1] == '!' {
+            match find_char(&chars, i, '>') {
+                Some(end) => {
+                    i = end + 1;
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        let tag_end = match find_tag_end(&chars, i) {
+            Some(end) => end,
+            None => break,
+        };
+        let raw_tag: String = chars[i + 1..tag_end].iter().collect();
+        i = tag_end + 1;
+
+        if let Some(name) = raw_tag.strip_prefix('/') {
+            tokens.push(Token::End {
+                name: name.trim().to_ascii_lowercase(),
+            });
+            continue;
+        }
+
+        let explicit_self_closing = raw_tag.trim_end().ends_with('/');
+        let raw_tag = raw_tag.trim_end().trim_end_matches('/');
+        let (name, attrs) = parse_tag(raw_tag);
+        let self_closing = explicit_self_closing || VOID_ELEMENTS.contains(&name.as_str());
+
+        // Content of script/style is not HTML; skip straight to its end tag,
+        // emitting the matching End ourselves since the real one was just
+        // skipped over rather than tokenized.
+        if matches!(name.as_str(), "script" | "style") {
+            if let Some(resume_at) = find_end_tag(&chars, i, &name) {
+                i = resume_at;
+            }
+            tokens.push(Token::Start {
+                name: name.clone(),
+                attrs,
+                self_closing: false,
+            });
+            tokens.push(Token::End { name });
+            continue;
+        }
+
+        tokens.push(Token::Start {
+            name,
+            attrs,
+            self_closing,
+        });
+    }
+
+    if !text_buf.is_empty() {
+        tokens.push(Token::Text(text_buf));
+    }
+
+    tokens
+}
+
+fn matches_at(chars: &[char], start: usize, needle: &str) -> bool {
+    let needle: Vec<char> = needle.chars().collect();
+    start + needle.len() <= chars.len() && chars[start..start + needle.len()] == needle[..]
+}
+
+fn find_sequence(chars: &[char], start: usize, needle: &str) -> Option<usize> {
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() || start > chars.len() {
+        return None;
+    }
+    (start..=chars.len().saturating_sub(needle.len()))
+        .find(|&i| chars[i..i + needle.len()] == needle[..])
+}
+
+fn find_char(chars: &[char], start: usize, target: char) -> Option<usize> {
+    (start..chars.len()).find(|&i| chars[i] == target)
+}
+
+/// Find the `>` that closes the tag starting at `chars[start]` (`<`),
+/// ignoring any `>` inside quoted attribute values.
+fn find_tag_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    let mut quote: Option<char> = None;
+    while i < chars.len() {
+        match quote {
+            Some(q) if chars[i] == q => quote = None,
+            Some(_) => {}
+            None if chars[i] == '"' || chars[i] == '\'' => quote = Some(chars[i]),
+            None if chars[i] == '>' => return Some(i),
+            None => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Find the position right after the closing `>` of `</tag_name>`, scanning
+/// forward from `from`.
+fn find_end_tag(chars: &[char], from: usize, tag_name: &str) -> Option<usize> {
+    let needle = format!("</{}", tag_name);
+    let needle_len = needle.chars().count();
+    let mut i = from;
+    while i + needle_len <= chars.len() {
+        let candidate: String = chars[i..i + needle_len].iter().collect();
+        if candidate.eq_ignore_ascii_case(&needle) {
+            return find_char(chars, i, '>').map(|gt| gt + 1);
+        }
+        i += 1;
+    }
+    None
+}
+
+fn parse_tag(raw: &str) -> (String, HashMap<String, String>) {
+    let raw = raw.trim();
+    let mut parts = raw.splitn(2, char::is_whitespace);
+    let name = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let attrs = parse_attrs(parts.next().unwrap_or_default());
+    (name, attrs)
+}
+
+fn parse_attrs(rest: &str) -> HashMap<String, String> {
+    let chars: Vec<char> = rest.chars().collect();
+    let mut attrs = HashMap::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        let name_start = i;
+        while i < chars.len() && chars[i] != '=' && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        let name: String = chars[name_start..i].iter().collect();
+        if name.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+
+        if i < chars.len() && chars[i] == '=' {
+            i += 1;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+
+            let value = if i < chars.len() && (chars[i] == '"' || chars[i] == '\'') {
+                let quote = chars[i];
+                i += 1;
+                let val_start = i;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                let value: String = chars[val_start..i].iter().collect();
+                i += 1;
+                value
+            } else {
+                let val_start = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                chars[val_start..i].iter().collect()
+            };
+
+            attrs.insert(name.to_ascii_lowercase(), decode_entities(&value));
+        } else {
+            attrs.insert(name.to_ascii_lowercase(), String::new());
+        }
+    }
+
+    attrs
+}
+
+/// Build a tree from a flat token stream, auto-closing tags left open when
+/// their parent (or the document) ends.
+fn build_tree(tokens: Vec<Token>) -> Vec<Node> {
+    let mut root: Vec<Node> = Vec::new();
+    let mut stack: Vec<(String, HashMap<String, String>, Vec<Node>)> = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Text(text) => push_node(&mut stack, &mut root, Node::Text(text)),
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                if self_closing {
+                    let node = Node::Element {
+                        tag: name,
+                        attrs,
+                        children: Vec::new(),
+                    };
+                    push_node(&mut stack, &mut root, node);
+                } else {
+                    stack.push((name, attrs, Vec::new()));
+                }
+            }
+            Token::End { name } => {
+                if let Some(pos) = stack.iter().rposition(|(tag, _, _)| *tag == name) {
+                    while stack.len() > pos {
+                        let (tag, attrs, children) = stack.pop().unwrap();
+                        let node = Node::Element {
+                            tag,
+                            attrs,
+                            children,
+                        };
+                        push_node(&mut stack, &mut root, node);
+                    }
+                }
+            }
+        }
+    }
+
+    while let Some((tag, attrs, children)) = stack.pop() {
+        let node = Node::Element {
+            tag,
+            attrs,
+            children,
+        };
+        push_node(&mut stack, &mut root, node);
+    }
+
+    root
+}
+
+fn push_node(
+    stack: &mut [(String, HashMap<String, String>, Vec<Node>)],
+    root: &mut Vec<Node>,
+    node: Node,
+) {
+    match stack.last_mut() {
+        Some((_, _, children)) => children.push(node),
+        None => root.push(node),
+    }
+}
+
+fn render_nodes(nodes: &[Node], email: &Email, list_stack: &mut Vec<ListCtx>) -> String {
+    nodes
+        .iter()
+        .map(|node| render_node(node, email, list_stack))
+        .collect()
+}
+
+fn render_node(node: &Node, email: &Email, list_stack: &mut Vec<ListCtx>) -> String {
+    let (tag, attrs, children) = match node {
+        Node::Text(text) => return decode_entities(&collapse_whitespace(text)),
+        Node::Element {
+            tag,
+            attrs,
+            children,
+        } => (tag.as_str(), attrs, children),
+    };
+
+    match tag {
+        "script" | "style" | "head" | "title" => String::new(),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level: usize = tag[1..].parse().unwrap_or(1);
+            let inner = render_nodes(children, email, list_stack);
+            format!("\n\n{} {}\n\n", "#".repeat(level), inner.trim())
+        }
+        "p" | "div" => {
+            let inner = render_nodes(children, email, list_stack);
+            let trimmed = inner.trim();
+            if trimmed.is_empty() {
+                String::new()
+            } else {
+                format!("\n\n{}\n\n", trimmed)
+            }
+        }
+        "br" => "  \n".to_string(),
+        "hr" => "\n\n---\n\n".to_string(),
+        "b" | "strong" => {
+            let inner = render_nodes(children, email, list_stack);
+            if inner.trim().is_empty() {
+                String::new()
+            } else {
+                format!("**{}**", inner)
+            }
+        }
+        "i" | "em" => {
+            let inner = render_nodes(children, email, list_stack);
+            if inner.trim().is_empty() {
+                String::new()
+            } else {
+                format!("_{}_", inner)
+            }
+        }
+        "a" => {
+            let inner = render_nodes(children, email, list_stack);
+            match attrs.get("href").filter(|href| !href.is_empty()) {
+                Some(href) => format!("[{}]({})", inner, href),
+                None => inner,
+            }
+        }
+        "img" => {
+            let src = attrs.get("src").cloned().unwrap_or_default();
+            let alt = attrs
+                .get("alt")
+                .cloned()
+                .filter(|alt| !alt.is_empty())
+                .or_else(|| {
+                    src.strip_prefix("cid:")
+                        .and_then(|id| super::find_attachment_by_cid(id, email))
+                        .and_then(|attachment| attachment.filename().map(str::to_string))
+                })
+                .unwrap_or_default();
+            format!("![{}]({})", alt, resolve_image_src(&src, email))
+        }
+        "ul" | "ol" => {
+            list_stack.push(ListCtx {
+                ordered: tag == "ol",
+                index: 0,
+            });
+            let inner = render_nodes(children, email, list_stack);
+            list_stack.pop();
+            format!("\n\n{}\n\n", inner.trim_end())
+        }
+        "li" => {
+            let depth = list_stack.len().saturating_sub(1);
+            let indent = "  ".repeat(depth);
+            let marker = match list_stack.last_mut() {
+                Some(ctx) if ctx.ordered => {
+                    ctx.index += 1;
+                    format!("{}. ", ctx.index)
+                }
+                _ => "- ".to_string(),
+            };
+            let inner = render_nodes(children, email, list_stack);
+            format!("\n{}{}{}", indent, marker, inner.trim())
+        }
+        "blockquote" => {
+            let inner = render_nodes(children, email, list_stack);
+            let quoted = inner
+                .trim()
+                .lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        ">".to_string()
+                    } else {
+                        format!("> {}", line)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("\n\n{}\n\n", quoted)
+        }
+        "pre" => {
+            let raw = render_text_only(children);
+            format!("\n\n```\n{}\n```\n\n", raw.trim_end_matches('\n'))
+        }
+        "code" => format!("`{}`", render_text_only(children)),
+        _ => render_nodes(children, email, list_stack),
+    }
+}
+
+/// Extract raw text content, ignoring tags entirely and without collapsing
+/// whitespace, for use inside `<pre>`/`<code>`.
+fn render_text_only(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(text) => decode_entities(text),
+            Node::Element { children, .. } => render_text_only(children),
+        })
+        .collect()
+}
+
+fn resolve_image_src(src: &str, email: &Email) -> String {
+    match src.strip_prefix("cid:") {
+        Some(id) => super::resolve_cid(id, email).unwrap_or_else(|| src.to_string()),
+        None => src.to_string(),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last_was_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !last_was_space {
+                out.push(' ');
+            }
+            last_was_space = true;
+        } else {
+            out.push(c);
+            last_was_space = false;
+        }
+    }
+    out
+}
+
+/// Collapse runs of 3+ newlines down to a single blank line
+fn collapse_blank_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut newline_run = 0;
+    for c in text.chars() {
+        if c == '\n' {
+            newline_run += 1;
+            if newline_run <= 2 {
+                out.push(c);
+            }
+        } else {
+            newline_run = 0;
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(amp_idx) = rest.find('&') {
+        out.push_str(&rest[..amp_idx]);
+        let after = &rest[amp_idx + 1..];
+        let end = after.find(|c: char| c == ';' || c.is_whitespace() || c == '&');
+
+        match end {
+            Some(end_idx) if after.as_bytes()[end_idx] == b';' => {
+                out.push_str(&decode_entity(&after[..end_idx]));
+                rest = &after[end_idx + 1..];
+            }
+            _ => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> String {
+    match entity {
+        "amp" => "&".to_string(),
+        "lt" => "<".to_string(),
+        "gt" => ">".to_string(),
+        "quot" => "\"".to_string(),
+        "apos" => "'".to_string(),
+        "nbsp" => " ".to_string(),
+        _ => {
+            let code = entity.strip_prefix('#').and_then(|rest| {
+                if let Some(hex) = rest.strip_prefix('x').or_else(|| rest.strip_prefix('X')) {
+                    u32::from_str_radix(hex, 16).ok()
+                } else {
+                    rest.parse::<u32>().ok()
+                }
+            });
+
+            match code.and_then(char::from_u32) {
+                Some(ch) => ch.to_string(),
+                None => format!("&{};", entity),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_type::ContentType;
+    use crate::eml::{Attachment, Body, Email, Header, User};
+
+    fn empty_email() -> Email {
+        Email {
+            header: Header {
+                from: User::new("", ""),
+                to: vec![],
+                cc: vec![],
+                subject: String::new(),
+                date: None,
+                message_id: None,
+            },
+            body: vec![],
+            attachments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_headings_and_emphasis() {
+        let email = empty_email();
+        let md = html_to_markdown("<h1>Title</h1><p>Some <b>bold</b> and <i>italic</i> text.</p>", &email);
+
+        assert!(md.contains("# Title"));
+        assert!(md.contains("**bold**"));
+        assert!(md.contains("_italic_"));
+    }
+
+    #[test]
+    fn test_links_and_lists() {
+        let email = empty_email();
+        let md = html_to_markdown(
+            "<ul><li>one</li><li><a href=\"https://example.com\">two</a></li></ul>",
+            &email,
+        );
+
+        assert!(md.contains("- one"));
+        assert!(md.contains("- [two](https://example.com)"));
+    }
+
+    #[test]
+    fn test_blockquote_and_code() {
+        let email = empty_email();
+        let md = html_to_markdown(
+            "<blockquote>quoted text</blockquote><pre><code>let x = 1;</code></pre>",
+            &email,
+        );
+
+        assert!(md.contains("> quoted text"));
+        assert!(md.contains("```\nlet x = 1;\n```"));
+    }
+
+    #[test]
+    fn test_img_resolves_cid() {
+        let mut email = empty_email();
+        email.body.push(Body {
+            content: String::new(),
+            content_type: "text/html".to_string(),
+            alternative_group: None,
+        });
+        email.attachments.push(Attachment {
+            raw: vec![9, 9, 9],
+            content_type: Some(ContentType::parse("image/png")),
+            content_id: Some("logo".to_string()),
+            content_disposition: None,
+        });
+
+        let md = html_to_markdown("<img src=\"cid:logo\" alt=\"Logo\">", &email);
+
+        assert!(md.starts_with("![Logo](data:image/png;base64,"));
+    }
+
+    #[test]
+    fn test_img_falls_back_to_attachment_filename_when_alt_missing() {
+        use crate::content_type::ContentDisposition;
+
+        let mut email = empty_email();
+        email.body.push(Body {
+            content: String::new(),
+            content_type: "text/html".to_string(),
+            alternative_group: None,
+        });
+        email.attachments.push(Attachment {
+            raw: vec![9, 9, 9],
+            content_type: Some(ContentType::parse("image/png")),
+            content_id: Some("logo".to_string()),
+            content_disposition: Some(ContentDisposition::parse(
+                "inline; filename=\"logo.png\"",
+            )),
+        });
+
+        let md = html_to_markdown("<img src=\"cid:logo\">", &email);
+
+        assert!(md.starts_with("![logo.png](data:image/png;base64,"));
+    }
+
+    #[test]
+    fn test_collapses_insignificant_whitespace() {
+        let email = empty_email();
+        let md = html_to_markdown("<p>Hello\n   World  </p>", &email);
+
+        assert!(md.contains("Hello World"));
+    }
+
+    #[test]
+    fn test_style_block_does_not_swallow_following_content() {
+        let email = empty_email();
+        let md = html_to_markdown("<style>.a{color:red}</style><p>Hello World</p>", &email);
+
+        assert!(md.contains("Hello World"));
+    }
+
+    #[test]
+    fn test_script_block_does_not_swallow_following_content() {
+        let email = empty_email();
+        let md = html_to_markdown(
+            "<script>var x = '<p>not real</p>';</script><p>Hello World</p>",
+            &email,
+        );
+
+        assert!(md.contains("Hello World"));
+        assert!(!md.contains("not real"));
+    }
+}
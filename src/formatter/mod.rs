@@ -1,8 +1,13 @@
 //! Markdown formatters for email content
 
+mod html;
 mod simple;
 
-use crate::eml::{Body, Email, Header};
+use std::collections::HashSet;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::eml::{Attachment, Body, Email, Header};
 
 pub use simple::{SimpleFormatter, SimpleHtmlFormatter};
 
@@ -18,16 +23,50 @@ pub trait Formatter {
     /// Format email header as markdown table
     fn format_header(&self, header: &Header) -> String;
 
-    /// Format all bodies
+    /// Format all bodies, picking a single representation within each
+    /// `multipart/alternative` group instead of emitting every one
     fn format_bodies(&self, bodies: &[Body], email: &Email) -> String {
-        bodies
-            .iter()
+        self.select_bodies(bodies)
+            .into_iter()
             .filter(|body| self.is_supported_content(body))
             .map(|body| self.format_body(body, email))
             .collect::<Vec<_>>()
             .join("\n")
     }
 
+    /// Reduce each alternative group to the formatter's preferred content
+    /// type, leaving non-alternative bodies untouched
+    fn select_bodies<'a>(&self, bodies: &'a [Body]) -> Vec<&'a Body> {
+        let mut selected = Vec::new();
+        let mut seen_groups = HashSet::new();
+
+        for body in bodies {
+            let Some(group) = body.alternative_group else {
+                selected.push(body);
+                continue;
+            };
+
+            if !seen_groups.insert(group) {
+                continue;
+            }
+
+            let preferred = bodies
+                .iter()
+                .filter(|b| b.alternative_group == Some(group))
+                .find(|b| b.content_type == self.preferred_content_type())
+                .unwrap_or(body);
+            selected.push(preferred);
+        }
+
+        selected
+    }
+
+    /// The content type this formatter prefers when choosing among
+    /// alternative representations of the same body
+    fn preferred_content_type(&self) -> &str {
+        "text/plain"
+    }
+
     /// Format a single body
     fn format_body(&self, body: &Body, email: &Email) -> String;
 
@@ -48,3 +87,32 @@ pub fn format_markdown(email: &Email, formatter_name: &str) -> String {
     let formatter = create_formatter(formatter_name);
     formatter.format(email)
 }
+
+/// Find the attachment whose `Content-ID` matches `id` (with or without
+/// angle brackets on either side), or `None` if no attachment matches.
+pub(crate) fn find_attachment_by_cid<'a>(id: &str, email: &'a Email) -> Option<&'a Attachment> {
+    let id = id.trim_start_matches('<').trim_end_matches('>');
+
+    email.attachments.iter().find(|attachment| {
+        attachment
+            .content_id
+            .as_deref()
+            .map(|cid| cid.trim_start_matches('<').trim_end_matches('>') == id)
+            .unwrap_or(false)
+    })
+}
+
+/// Resolve a single `Content-ID` reference (with or without angle brackets)
+/// to a `data:` URI using the matching attachment, or `None` if no
+/// attachment has a matching `Content-ID`.
+pub(crate) fn resolve_cid(id: &str, email: &Email) -> Option<String> {
+    let attachment = find_attachment_by_cid(id, email)?;
+
+    let mime_type = attachment
+        .content_type
+        .as_ref()
+        .map(|ct| ct.mime_type())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let base64_data = STANDARD.encode(&attachment.raw);
+    Some(format!("data:{};base64,{}", mime_type, base64_data))
+}
@@ -32,7 +32,7 @@ impl SimpleFormatter {
         for attachment in &email.attachments {
             if let Some(ref ct) = attachment.content_type {
                 if ct.main_type == "image" {
-                    if let Some(name) = ct.parameters.get("name") {
+                    if let Some(name) = attachment.filename() {
                         let placeholder = format!("[image: {}]", name);
                         let base64_data = STANDARD.encode(&attachment.raw);
                         let data_uri = format!(
@@ -50,6 +50,37 @@ impl SimpleFormatter {
         result
     }
 
+    /// Resolve `cid:<id>` references (e.g. `src="cid:..."` in HTML bodies) to
+    /// `data:<mime>;base64,<...>` URIs using the matching attachment's
+    /// Content-ID, falling back to leaving the reference untouched when no
+    /// attachment matches.
+    fn resolve_cid_references(content: &str, email: &Email) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut rest = content;
+
+        while let Some(idx) = rest.find("cid:") {
+            result.push_str(&rest[..idx]);
+            let after = &rest[idx + "cid:".len()..];
+            let end = after
+                .find(|c: char| matches!(c, '"' | '\'' | ')' | ']' | '>' | '<') || c.is_whitespace())
+                .unwrap_or(after.len());
+            let raw_ref = &after[..end];
+
+            match super::resolve_cid(raw_ref, email) {
+                Some(data_uri) => result.push_str(&data_uri),
+                None => {
+                    result.push_str("cid:");
+                    result.push_str(raw_ref);
+                }
+            }
+
+            rest = &after[end..];
+        }
+
+        result.push_str(rest);
+        result
+    }
+
     fn strip_content(content: &str) -> String {
         content
             .replace("\r\n\r\n", "\n")
@@ -78,6 +109,7 @@ impl Formatter for SimpleFormatter {
 
     fn format_body(&self, body: &Body, email: &Email) -> String {
         let content = Self::replace_attachments(&body.content, email);
+        let content = Self::resolve_cid_references(&content, email);
         Self::strip_content(&content)
     }
 
@@ -86,7 +118,8 @@ impl Formatter for SimpleFormatter {
     }
 }
 
-/// HTML-aware formatter (currently same behavior as SimpleFormatter)
+/// Formatter that renders `text/html` bodies as real Markdown, falling back
+/// to the plain-text path for anything else
 pub struct SimpleHtmlFormatter;
 
 impl Formatter for SimpleHtmlFormatter {
@@ -95,12 +128,18 @@ impl Formatter for SimpleHtmlFormatter {
     }
 
     fn format_body(&self, body: &Body, email: &Email) -> String {
-        SimpleFormatter.format_body(body, email)
+        match body.content_type.as_str() {
+            "text/html" => super::html::html_to_markdown(&body.content, email),
+            _ => SimpleFormatter.format_body(body, email),
+        }
     }
 
     fn is_supported_content(&self, body: &Body) -> bool {
-        // Currently same as SimpleFormatter per Python implementation
-        body.content_type == "text/plain"
+        matches!(body.content_type.as_str(), "text/plain" | "text/html")
+    }
+
+    fn preferred_content_type(&self) -> &str {
+        "text/html"
     }
 }
 
@@ -123,10 +162,12 @@ mod tests {
                         .with_ymd_and_hms(2024, 1, 1, 12, 0, 0)
                         .unwrap(),
                 ),
+                message_id: None,
             },
             body: vec![Body {
                 content: "Hello, World!".to_string(),
                 content_type: "text/plain".to_string(),
+                alternative_group: None,
             }],
             attachments: vec![],
         }
@@ -161,4 +202,80 @@ mod tests {
         assert!(output.contains("|||"));
         assert!(output.contains("Hello, World!"));
     }
+
+    #[test]
+    fn test_alternative_group_picks_plain_text() {
+        let mut email = create_test_email();
+        email.body = vec![
+            Body {
+                content: "Plain version".to_string(),
+                content_type: "text/plain".to_string(),
+                alternative_group: Some(0),
+            },
+            Body {
+                content: "<p>HTML version</p>".to_string(),
+                content_type: "text/html".to_string(),
+                alternative_group: Some(0),
+            },
+        ];
+
+        let formatter = SimpleFormatter;
+        let output = formatter.format(&email);
+
+        assert!(output.contains("Plain version"));
+        assert!(!output.contains("HTML version"));
+    }
+
+    #[test]
+    fn test_replace_attachments_prefers_content_disposition_filename() {
+        use crate::content_type::{ContentDisposition, ContentType};
+
+        let mut email = create_test_email();
+        email.body[0].content = "[image: from-disposition.png]".to_string();
+        email.attachments.push(crate::eml::Attachment {
+            raw: vec![1, 2, 3],
+            content_type: Some(ContentType::parse("image/png; name=\"from-content-type.png\"")),
+            content_id: None,
+            content_disposition: Some(ContentDisposition::parse(
+                "inline; filename=\"from-disposition.png\"",
+            )),
+        });
+
+        let formatter = SimpleFormatter;
+        let body = formatter.format_body(&email.body[0], &email);
+
+        let expected_data = format!("data:image/png;base64,{}", STANDARD.encode([1, 2, 3]));
+        assert!(body.contains(&expected_data));
+    }
+
+    #[test]
+    fn test_resolve_cid_references() {
+        use crate::content_type::ContentType;
+
+        let mut email = create_test_email();
+        email.body[0].content = "<img src=\"cid:logo123\">".to_string();
+        email.attachments.push(crate::eml::Attachment {
+            raw: vec![1, 2, 3],
+            content_type: Some(ContentType::parse("image/png")),
+            content_id: Some("logo123".to_string()),
+            content_disposition: None,
+        });
+
+        let formatter = SimpleFormatter;
+        let body = formatter.format_body(&email.body[0], &email);
+
+        let expected_data = format!("data:image/png;base64,{}", STANDARD.encode([1, 2, 3]));
+        assert!(body.contains(&expected_data));
+    }
+
+    #[test]
+    fn test_resolve_cid_references_no_match() {
+        let mut email = create_test_email();
+        email.body[0].content = "<img src=\"cid:missing\">".to_string();
+
+        let formatter = SimpleFormatter;
+        let body = formatter.format_body(&email.body[0], &email);
+
+        assert!(body.contains("cid:missing"));
+    }
 }
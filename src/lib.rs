@@ -3,6 +3,7 @@
 //! This crate provides functionality to parse EML (email) files
 //! and convert them to Markdown format.
 
+pub mod batch;
 pub mod content_type;
 pub mod eml;
 pub mod formatter;
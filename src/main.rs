@@ -2,47 +2,104 @@
 
 use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 
-use eml2md::{parse_eml, formatter::format_markdown};
+use eml2md::batch::{run_batch, MailSource};
+use eml2md::{formatter::format_markdown, parse_eml};
 
 /// Convert EML files to Markdown
 #[derive(Parser, Debug)]
 #[command(name = "eml2md", version, about, long_about = None)]
 struct Args {
-    /// Input EML file
-    #[arg(short, long, required = true)]
-    input: PathBuf,
+    /// Input EML file (single-file mode)
+    #[arg(short, long)]
+    input: Option<PathBuf>,
 
-    /// Output Markdown file
-    #[arg(short, long, required = true)]
-    output: PathBuf,
+    /// Output Markdown file (single-file mode)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
 
     /// Output format: "simple" or "html"
     #[arg(short, long, default_value = "simple")]
     format: String,
+
+    /// Maildir directory tree to ingest (batch mode)
+    #[arg(long)]
+    input_dir: Option<PathBuf>,
+
+    /// mbox file to ingest (batch mode)
+    #[arg(long)]
+    mbox: Option<PathBuf>,
+
+    /// Directory to write one Markdown file per message into (batch mode)
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// Optional SQLite database to populate with per-message metadata (batch mode)
+    #[arg(long)]
+    index_db: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    if args.input_dir.is_some() || args.mbox.is_some() {
+        return run_batch_mode(&args);
+    }
+
+    let input = args
+        .input
+        .context("--input is required unless --input-dir or --mbox is given")?;
+    let output = args
+        .output
+        .context("--output is required unless --input-dir or --mbox is given")?;
+
     // Parse the EML file
-    let email = parse_eml(&args.input)
-        .with_context(|| format!("Failed to parse EML file: {}", args.input.display()))?;
+    let email = parse_eml(&input)
+        .with_context(|| format!("Failed to parse EML file: {}", input.display()))?;
 
     // Format as markdown
     let markdown = format_markdown(&email, &args.format);
 
     // Write output
-    std::fs::write(&args.output, &markdown)
-        .with_context(|| format!("Failed to write output file: {}", args.output.display()))?;
+    std::fs::write(&output, &markdown)
+        .with_context(|| format!("Failed to write output file: {}", output.display()))?;
 
     println!(
         "Successfully converted {} to {}",
-        args.input.display(),
-        args.output.display()
+        input.display(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+fn run_batch_mode(args: &Args) -> Result<()> {
+    let source = match (&args.input_dir, &args.mbox) {
+        (Some(dir), None) => MailSource::Maildir(dir.clone()),
+        (None, Some(mbox)) => MailSource::Mbox(mbox.clone()),
+        (Some(_), Some(_)) => bail!("--input-dir and --mbox are mutually exclusive"),
+        (None, None) => unreachable!("run_batch_mode requires --input-dir or --mbox"),
+    };
+
+    let output_dir = args
+        .output_dir
+        .clone()
+        .context("--output-dir is required in batch mode")?;
+
+    let report = run_batch(source, &output_dir, &args.format, args.index_db.as_deref())?;
+
+    println!(
+        "Converted {}/{} messages to {}",
+        report.succeeded,
+        report.total,
+        output_dir.display()
     );
 
+    for (label, error) in &report.failed {
+        eprintln!("Failed to convert {}: {}", label, error);
+    }
+
     Ok(())
 }